@@ -2,9 +2,16 @@ use crate::objects::Ctx;
 use crate::plugins::{choose_edits, Plugin, PluginCtx};
 use crate::state::{PerMapUI, PluginsPerMap};
 use ezgui::{GfxCtx, Wizard, WrappedWizard};
+use map_model;
 use map_model::Map;
 use sim::SimFlags;
+use std::time::{Duration, Instant};
 
+// How often to check whether there are unsaved edits worth backing up.
+const DEFAULT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(180);
+
+// Only runs while the "manage map edits" wizard is open; disappears (and stops blocking other
+// plugins) as soon as it's dismissed, same as any other modal wizard plugin.
 pub struct EditsManager {
     wizard: Wizard,
 }
@@ -32,6 +39,7 @@ impl Plugin for EditsManager {
             &mut ctx.primary.current_flags,
             &ctx.primary.map,
             &mut new_primary,
+            &mut primary_plugins.autosave_edits,
             self.wizard.wrap(ctx.input, ctx.canvas),
         )
         .is_some()
@@ -51,34 +59,106 @@ impl Plugin for EditsManager {
     }
 }
 
+// Lives for the whole map, independent of whether the "manage map edits" wizard is open, so it
+// can autosave in the background without occupying the input slot other plugins need.
+pub struct AutoSaveEdits {
+    last_checked: Instant,
+    last_saved: map_model::MapEdits,
+    interval: Duration,
+}
+
+impl AutoSaveEdits {
+    pub fn new(map: &Map) -> AutoSaveEdits {
+        AutoSaveEdits {
+            last_checked: Instant::now(),
+            last_saved: map.get_edits().clone(),
+            interval: DEFAULT_AUTOSAVE_INTERVAL,
+        }
+    }
+}
+
+impl Plugin for AutoSaveEdits {
+    fn blocking_event_with_plugins(
+        &mut self,
+        ctx: &mut PluginCtx,
+        _primary_plugins: &mut PluginsPerMap,
+    ) -> bool {
+        if self.last_checked.elapsed() >= self.interval {
+            self.last_checked = Instant::now();
+
+            let edits = ctx.primary.map.get_edits();
+            if edits != &self.last_saved {
+                let mut autosave = edits.clone();
+                autosave.edits_name = format!("autosave_{}", edits.edits_name);
+                autosave.save();
+                info!("Autosaved unsaved map edits as {}", autosave.edits_name);
+                self.last_saved = edits.clone();
+            }
+        }
+        // Never blocks other plugins, so it never has to go away on its own.
+        false
+    }
+
+    fn draw(&self, _g: &mut GfxCtx, _ctx: &Ctx) {}
+}
+
 fn manage_edits(
     current_flags: &mut SimFlags,
     map: &Map,
     new_primary: &mut Option<(PerMapUI, PluginsPerMap)>,
+    autosave: &mut AutoSaveEdits,
     mut wizard: WrappedWizard,
 ) -> Option<()> {
-    // TODO Indicate how many edits are there / if there are any unsaved edits
     let load = "Load other map edits";
     let save_new = "Save these new map edits";
     let save_existing = &format!("Save {}", current_flags.edits_name);
-    let choices: Vec<&str> = if current_flags.edits_name == "no_edits" {
+    let change_autosave = "Change autosave interval";
+    let mut choices: Vec<&str> = if current_flags.edits_name == "no_edits" {
         vec![save_new, load]
     } else {
         vec![save_existing, load]
     };
+    choices.push(change_autosave);
 
     // Slow to create this every tick just to get the description? It's actually frozen once the
     // wizard is started...
     let mut edits = map.get_edits().clone();
     edits.edits_name = edits.edits_name.clone();
 
-    match wizard
-        .choose_string(&format!("Manage {}", edits.describe()), choices)?
-        .as_str()
-    {
+    let mut prompt = format!("Manage {}", edits.describe());
+    if edits_are_unsaved(map) {
+        prompt = format!("{} (unsaved edits!)", prompt);
+    }
+
+    match wizard.choose_string(&prompt, choices)?.as_str() {
         x if x == save_new => {
             let name = wizard.input_string("Name the map edits")?;
             edits.edits_name = name.clone();
+
+            // Optionally let the author explain what this proposal does and where it came from,
+            // so somebody loading it later can tell a curated proposal from raw player edits.
+            if wizard
+                .choose_string(
+                    "Describe these edits for other players?",
+                    vec!["Yes", "No"],
+                )?
+                == "Yes"
+            {
+                let mut description = Vec::new();
+                loop {
+                    let line = wizard.input_string("Describe these edits (blank line to finish)")?;
+                    if line.is_empty() {
+                        break;
+                    }
+                    description.push(line);
+                }
+                edits.proposal_description = description;
+
+                let link =
+                    wizard.input_string("Link to more info about this proposal (leave blank to clear it)")?;
+                edits.proposal_link = if link.is_empty() { None } else { Some(link) };
+            }
+
             edits.save();
             // No need to reload everything
             current_flags.edits_name = name;
@@ -98,6 +178,27 @@ fn manage_edits(
             *new_primary = Some(PerMapUI::new(flags, None, true));
             Some(())
         }
+        x if x == change_autosave => {
+            let seconds = wizard
+                .input_string(&format!(
+                    "Autosave every how many seconds? (currently {})",
+                    autosave.interval.as_secs()
+                ))?
+                .parse::<u64>()
+                .ok()?;
+            autosave.interval = Duration::from_secs(seconds.max(1));
+            Some(())
+        }
         _ => unreachable!(),
     }
 }
+
+// True if the in-memory edits have diverged from whatever's saved on disk under their current
+// name, so there's something an autosave (or an explicit save) would actually capture.
+fn edits_are_unsaved(map: &Map) -> bool {
+    let edits = map.get_edits();
+    match map_model::MapEdits::load(&edits.edits_name) {
+        Some(ref saved) => saved != edits,
+        None => !edits.is_empty(),
+    }
+}
@@ -4,11 +4,13 @@ use colors::Colors;
 use control::ControlMap;
 use dimensioned::si;
 use ezgui::GfxCtx;
-use geom::{Bounds, Circle, Line, Polygon, Pt2D};
+use geom::{Bounds, Circle, GPSBounds, Line, PolyLine, Polygon, Pt2D};
 use map_model;
-use map_model::{geometry, LaneID};
+use map_model::{geometry, DrivingSide, IntersectionID, LaneID};
 use objects::{Ctx, ID};
 use render::{RenderOptions, Renderable, PARCEL_BOUNDARY_THICKNESS};
+use serde_json::{json, Value};
+use std::collections::HashMap;
 
 const MIN_ZOOM_FOR_LANE_MARKERS: f64 = 5.0;
 
@@ -18,23 +20,39 @@ struct Marking {
     color: Colors,
     thickness: f64,
     round: bool,
+    // Tags the marking's kind for consumers (like GeoJSON export) that care what it represents,
+    // not just how to draw it.
+    kind: &'static str,
 }
 
 #[derive(Debug)]
 pub struct DrawLane {
     pub id: LaneID,
+    lane_type: map_model::LaneType,
     pub polygon: Polygon,
     start_crossing: Line,
     end_crossing: Line,
     markings: Vec<Marking>,
+    // Fills the gap with a neighboring sidewalk at each end of this lane, if it's a sidewalk.
+    corners: Vec<Polygon>,
 
     // TODO pretty temporary
     draw_id_at: Vec<Pt2D>,
 }
 
 impl DrawLane {
-    pub fn new(lane: &map_model::Lane, map: &map_model::Map, control_map: &ControlMap) -> DrawLane {
+    // `corners` is the whole map's sidewalk corners, keyed by the lanes they touch; compute it
+    // once per map with `calculate_all_sidewalk_corners` and share it across every DrawLane, since
+    // re-deriving and re-sorting one intersection's crossings from scratch for each touching lane
+    // is quadratic busywork at load time.
+    pub fn new(
+        lane: &map_model::Lane,
+        map: &map_model::Map,
+        control_map: &ControlMap,
+        corners: &HashMap<LaneID, Vec<Polygon>>,
+    ) -> DrawLane {
         let road = map.get_r(lane.parent);
+        let driving_side = map.get_config().driving_side;
         let start = new_perp_line(lane.first_line(), geometry::LANE_THICKNESS);
         let end = new_perp_line(lane.last_line().reverse(), geometry::LANE_THICKNESS);
         let polygon = lane
@@ -48,6 +66,7 @@ impl DrawLane {
                 color: Colors::RoadOrientation,
                 thickness: geometry::BIG_ARROW_THICKNESS,
                 round: true,
+                kind: "road_orientation",
             });
         }
         match lane.lane_type {
@@ -55,10 +74,10 @@ impl DrawLane {
                 markings.push(calculate_sidewalk_lines(lane));
             }
             map_model::LaneType::Parking => {
-                markings.push(calculate_parking_lines(lane));
+                markings.push(calculate_parking_lines(lane, driving_side));
             }
             map_model::LaneType::Driving => {
-                for m in calculate_driving_lines(lane, road) {
+                for m in calculate_driving_lines(lane, road, driving_side) {
                     markings.push(m);
                 }
             }
@@ -72,8 +91,10 @@ impl DrawLane {
 
         DrawLane {
             id: lane.id,
+            lane_type: lane.lane_type,
             polygon,
             markings,
+            corners: corners.get(&lane.id).cloned().unwrap_or_else(Vec::new),
             start_crossing: start,
             end_crossing: end,
             draw_id_at: calculate_id_positions(lane).unwrap_or(Vec::new()),
@@ -107,6 +128,70 @@ impl DrawLane {
     pub fn get_start_crossing(&self) -> &Line {
         &self.start_crossing
     }
+
+    // Dumps this lane's polygon and markings as a GeoJSON FeatureCollection, projected back to
+    // lon/lat using the map's GPS bounds. Gives external GIS tools (and tests, without needing a
+    // GfxCtx) a way to inspect what the `calculate_*` marking generators actually produced.
+    pub fn to_geojson(&self, gps_bounds: &GPSBounds) -> Value {
+        let mut features = vec![json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [pts_to_lon_lat(&self.polygon.points(), gps_bounds)],
+            },
+            "properties": {
+                "type": "lane",
+                "lane_id": self.id.0,
+                "lane_type": lane_type_tag(self.lane_type),
+            },
+        })];
+
+        for m in &self.markings {
+            // MultiLineString, not LineString: a Marking's lines can be disjoint (separate dashes,
+            // parking pips), and a single LineString would connect each one's endpoint to the next
+            // one's start, drawing a zigzag through gaps that aren't actually part of the
+            // marking.
+            features.push(json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiLineString",
+                    "coordinates": m.lines
+                        .iter()
+                        .map(|l| pts_to_lon_lat(&vec![l.pt1(), l.pt2()], gps_bounds))
+                        .collect::<Vec<_>>(),
+                },
+                "properties": {
+                    "type": "marking",
+                    "marking_kind": m.kind,
+                    "lane_id": self.id.0,
+                },
+            }));
+        }
+
+        json!({
+            "type": "FeatureCollection",
+            "features": features,
+        })
+    }
+}
+
+fn pts_to_lon_lat(pts: &Vec<Pt2D>, gps_bounds: &GPSBounds) -> Vec<[f64; 2]> {
+    pts.iter()
+        .map(|pt| {
+            let gps = pt.to_gps(gps_bounds);
+            [gps.longitude, gps.latitude]
+        })
+        .collect()
+}
+
+// Short machine-readable tag for a LaneType, matching the `kind` convention Marking uses.
+fn lane_type_tag(lane_type: map_model::LaneType) -> &'static str {
+    match lane_type {
+        map_model::LaneType::Driving => "driving",
+        map_model::LaneType::Parking => "parking",
+        map_model::LaneType::Sidewalk => "sidewalk",
+        map_model::LaneType::Biking => "biking",
+    }
 }
 
 impl Renderable for DrawLane {
@@ -129,6 +214,9 @@ impl Renderable for DrawLane {
             default
         });
         g.draw_polygon(color, &self.polygon);
+        for p in &self.corners {
+            g.draw_polygon(color, p);
+        }
 
         if opts.cam_zoom >= MIN_ZOOM_FOR_LANE_MARKERS {
             for m in &self.markings {
@@ -148,11 +236,15 @@ impl Renderable for DrawLane {
     }
 
     fn get_bounds(&self) -> Bounds {
-        self.polygon.get_bounds()
+        let mut b = self.polygon.get_bounds();
+        for p in &self.corners {
+            b.union(p.get_bounds());
+        }
+        b
     }
 
     fn contains_pt(&self, pt: Pt2D) -> bool {
-        self.polygon.contains_pt(pt)
+        self.polygon.contains_pt(pt) || self.corners.iter().any(|p| p.contains_pt(pt))
     }
 
     fn tooltip_lines(&self, map: &map_model::Map) -> Vec<String> {
@@ -213,21 +305,33 @@ fn calculate_sidewalk_lines(lane: &map_model::Lane) -> Marking {
         color: Colors::SidewalkMarking,
         thickness: 0.25,
         round: false,
+        kind: "sidewalk_tile",
     }
 }
 
-fn calculate_parking_lines(lane: &map_model::Lane) -> Marking {
+// On the right-hand side of the road, the outside of the lane is 270 degrees from the direction
+// of travel. Mirror that for left-hand-traffic maps like Australia's.
+fn parking_perp_degrees(driving_side: DrivingSide) -> f64 {
+    match driving_side {
+        DrivingSide::Right => 270.0,
+        DrivingSide::Left => 90.0,
+    }
+}
+
+fn calculate_parking_lines(lane: &map_model::Lane, driving_side: DrivingSide) -> Marking {
     // meters, but the dims get annoying below to remove
     // TODO make Pt2D natively understand meters, projecting away by an angle
     let leg_length = 1.0;
 
+    let perp_degrees = parking_perp_degrees(driving_side);
+
     let mut lines = Vec::new();
     let num_spots = lane.number_parking_spots();
     if num_spots > 0 {
         for idx in 0..=num_spots {
             let (pt, lane_angle) =
                 lane.dist_along(map_model::PARKING_SPOT_LENGTH * (1.0 + idx as f64));
-            let perp_angle = lane_angle.rotate_degs(270.0);
+            let perp_angle = lane_angle.rotate_degs(perp_degrees);
             // Find the outside of the lane. Actually, shift inside a little bit, since the line will
             // have thickness, but shouldn't really intersect the adjacent line when drawn.
             let t_pt = pt.project_away(geometry::LANE_THICKNESS * 0.4, perp_angle);
@@ -248,21 +352,32 @@ fn calculate_parking_lines(lane: &map_model::Lane) -> Marking {
         color: Colors::ParkingMarking,
         thickness: 0.25,
         round: false,
+        kind: "parking_pip",
     }
 }
 
-fn calculate_driving_lines(lane: &map_model::Lane, parent: &map_model::Road) -> Option<Marking> {
+// On right-hand-traffic maps, the separator belongs on the driver's left, so project left by
+// reversing the points. On left-hand-traffic maps, it belongs on the right, so keep them as-is.
+fn driving_divider_center_pts(lane_center_pts: &PolyLine, driving_side: DrivingSide) -> PolyLine {
+    match driving_side {
+        DrivingSide::Right => lane_center_pts.reversed(),
+        DrivingSide::Left => lane_center_pts.clone(),
+    }
+}
+
+fn calculate_driving_lines(
+    lane: &map_model::Lane,
+    parent: &map_model::Road,
+    driving_side: DrivingSide,
+) -> Option<Marking> {
     // The rightmost lanes don't have dashed white lines.
     if parent.dir_and_offset(lane.id).1 == 0 {
         return None;
     }
 
-    // Project left, so reverse the points.
-    let center_pts = lane.lane_center_pts.reversed();
+    let center_pts = driving_divider_center_pts(&lane.lane_center_pts, driving_side);
     let lane_edge_pts = center_pts.shift_blindly(geometry::LANE_THICKNESS / 2.0);
 
-    // This is an incredibly expensive way to compute dashed polyines, and it doesn't follow bends
-    // properly. Just a placeholder.
     let lane_len = lane_edge_pts.length();
     let dash_separation = 2.0 * si::M;
     let dash_len = 1.0 * si::M;
@@ -274,9 +389,7 @@ fn calculate_driving_lines(lane: &map_model::Lane, parent: &map_model::Road) ->
             break;
         }
 
-        let (pt1, _) = lane_edge_pts.dist_along(start);
-        let (pt2, _) = lane_edge_pts.dist_along(start + dash_len);
-        lines.push(Line::new(pt1, pt2));
+        lines.extend(slice_polyline(&lane_edge_pts, start, start + dash_len));
         start += dash_len + dash_separation;
     }
 
@@ -285,9 +398,43 @@ fn calculate_driving_lines(lane: &map_model::Lane, parent: &map_model::Road) ->
         color: Colors::DrivingLaneMarking,
         thickness: 0.25,
         round: false,
+        kind: "driving_divider",
     })
 }
 
+// Slices a polyline between two distances along it, preserving every interior vertex so that
+// whatever uses the result (like dashed lane markings) bends with the polyline instead of cutting
+// across it with one straight Line.
+fn slice_polyline(pts: &PolyLine, start: si::Meter<f64>, end: si::Meter<f64>) -> Vec<Line> {
+    let mut result = Vec::new();
+    let mut dist_so_far = 0.0 * si::M;
+
+    for line in pts.lines() {
+        let seg_start = dist_so_far;
+        let seg_end = dist_so_far + line.length();
+        dist_so_far = seg_end;
+
+        // This segment doesn't overlap [start, end] at all.
+        if seg_end <= start || seg_start >= end {
+            continue;
+        }
+
+        let pt1 = if start <= seg_start {
+            line.pt1()
+        } else {
+            line.dist_along(start - seg_start)
+        };
+        let pt2 = if end >= seg_end {
+            line.pt2()
+        } else {
+            line.dist_along(end - seg_start)
+        };
+        result.push(Line::new(pt1, pt2));
+    }
+
+    result
+}
+
 fn calculate_stop_sign_line(lane: &map_model::Lane, control_map: &ControlMap) -> Option<Marking> {
     if control_map.stop_signs[&lane.dst_i].is_priority_lane(lane.id) {
         return None;
@@ -304,6 +451,7 @@ fn calculate_stop_sign_line(lane: &map_model::Lane, control_map: &ControlMap) ->
         color: Colors::StopSignMarking,
         thickness: 0.45,
         round: true,
+        kind: "stop_line",
     })
 }
 
@@ -317,3 +465,259 @@ fn calculate_id_positions(lane: &map_model::Lane) -> Option<Vec<Pt2D>> {
     let (pt2, _) = lane.safe_dist_along(2.0 * geometry::LANE_THICKNESS * si::M)?;
     Some(vec![pt1, pt2])
 }
+
+// Computes every sidewalk corner polygon in the map, once per intersection, and hands each
+// polygon to the (up to two) lanes whose crossing lines bound it. Call this once up front and
+// pass the result into every DrawLane::new, instead of having each touching lane rebuild and
+// re-sort the whole intersection's crossings from scratch.
+pub fn calculate_all_sidewalk_corners(map: &map_model::Map) -> HashMap<LaneID, Vec<Polygon>> {
+    let mut corners: HashMap<LaneID, Vec<Polygon>> = HashMap::new();
+    for i in map.all_intersections() {
+        for (lane1, lane2, polygon) in sidewalk_corners_at(map, i.id) {
+            corners.entry(lane1).or_insert_with(Vec::new).push(polygon.clone());
+            corners.entry(lane2).or_insert_with(Vec::new).push(polygon);
+        }
+    }
+    corners
+}
+
+// Fills the gaps between adjacent sidewalks around the intersection `at`, skipping adjacent pairs
+// that belong to the same road (they're just its two edges, not a corner to fill).
+fn sidewalk_corners_at(
+    map: &map_model::Map,
+    at: IntersectionID,
+) -> Vec<(LaneID, LaneID, Polygon)> {
+    let i = map.get_i(at);
+    let mut edges: Vec<(f64, map_model::RoadID, LaneID, Line)> = Vec::new();
+    for l in i.incoming_lanes.iter().chain(i.outgoing_lanes.iter()) {
+        let lane = map.get_l(*l);
+        if lane.lane_type != map_model::LaneType::Sidewalk {
+            continue;
+        }
+        let crossing = if lane.dst_i == at {
+            new_perp_line(lane.last_line().reverse(), geometry::LANE_THICKNESS)
+        } else {
+            new_perp_line(lane.first_line(), geometry::LANE_THICKNESS)
+        };
+        let mid = crossing.dist_along(crossing.length() / 2.0);
+        let angle = (mid.y() - i.point.y()).atan2(mid.x() - i.point.x());
+        edges.push((angle, lane.parent, lane.id, crossing));
+    }
+    corners_from_sorted_edges(edges, i.point)
+}
+
+// Sorts the crossing lines by angle around `center`, then fills the gap between each adjacent
+// pair (wrapping around), skipping pairs that belong to the same road. Split out from
+// sidewalk_corners_at so the sort/pair/same-road-skip logic can be tested against a handful of
+// fake edges, without needing a real map_model::Map to build them from.
+fn corners_from_sorted_edges(
+    mut edges: Vec<(f64, map_model::RoadID, LaneID, Line)>,
+    center: Pt2D,
+) -> Vec<(LaneID, LaneID, Polygon)> {
+    edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut corners = Vec::new();
+    for idx in 0..edges.len() {
+        let (_, road1, lane1, ref crossing1) = edges[idx];
+        let (_, road2, lane2, ref crossing2) = edges[(idx + 1) % edges.len()];
+        if road1 == road2 {
+            continue;
+        }
+
+        let (inner1, outer1) = inner_and_outer(crossing1, center);
+        let (inner2, outer2) = inner_and_outer(crossing2, center);
+        corners.push((
+            lane1,
+            lane2,
+            Polygon::new(&vec![outer1, inner1, inner2, outer2]),
+        ));
+    }
+    corners
+}
+
+// Of a crossing line's two points, returns (the one nearer the intersection center, the other).
+fn inner_and_outer(crossing: &Line, center: Pt2D) -> (Pt2D, Pt2D) {
+    let d1 = (crossing.pt1().x() - center.x()).hypot(crossing.pt1().y() - center.y());
+    let d2 = (crossing.pt2().x() - center.x()).hypot(crossing.pt2().y() - center.y());
+    if d1 < d2 {
+        (crossing.pt1(), crossing.pt2())
+    } else {
+        (crossing.pt2(), crossing.pt1())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polyline(pts: Vec<(f64, f64)>) -> PolyLine {
+        PolyLine::new(pts.into_iter().map(|(x, y)| Pt2D::new(x, y)).collect())
+    }
+
+    #[test]
+    fn parking_perp_degrees_mirrors_by_driving_side() {
+        assert_eq!(parking_perp_degrees(DrivingSide::Right), 270.0);
+        assert_eq!(parking_perp_degrees(DrivingSide::Left), 90.0);
+    }
+
+    #[test]
+    fn driving_divider_center_pts_reverses_only_for_right_hand_traffic() {
+        let pts = polyline(vec![(0.0, 0.0), (10.0, 0.0)]);
+
+        let right = driving_divider_center_pts(&pts, DrivingSide::Right);
+        let right_line = right.lines().into_iter().next().unwrap();
+        assert_eq!(right_line.pt1(), Pt2D::new(10.0, 0.0));
+        assert_eq!(right_line.pt2(), Pt2D::new(0.0, 0.0));
+
+        let left = driving_divider_center_pts(&pts, DrivingSide::Left);
+        let left_line = left.lines().into_iter().next().unwrap();
+        assert_eq!(left_line.pt1(), Pt2D::new(0.0, 0.0));
+        assert_eq!(left_line.pt2(), Pt2D::new(10.0, 0.0));
+    }
+
+    #[test]
+    fn slice_polyline_dash_wholly_inside_one_segment() {
+        let pts = polyline(vec![(0.0, 0.0), (100.0, 0.0)]);
+        let lines = slice_polyline(&pts, 10.0 * si::M, 20.0 * si::M);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].pt1(), Pt2D::new(10.0, 0.0));
+        assert_eq!(lines[0].pt2(), Pt2D::new(20.0, 0.0));
+    }
+
+    #[test]
+    fn slice_polyline_dash_spanning_a_bend() {
+        // Bends 90 degrees at (50, 0); the dash straddles that vertex.
+        let pts = polyline(vec![(0.0, 0.0), (50.0, 0.0), (50.0, 50.0)]);
+        let lines = slice_polyline(&pts, 40.0 * si::M, 60.0 * si::M);
+        // One Line per original segment the dash touches, so the result bends with the polyline.
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].pt1(), Pt2D::new(40.0, 0.0));
+        assert_eq!(lines[0].pt2(), Pt2D::new(50.0, 0.0));
+        assert_eq!(lines[1].pt1(), Pt2D::new(50.0, 0.0));
+        assert_eq!(lines[1].pt2(), Pt2D::new(50.0, 10.0));
+    }
+
+    #[test]
+    fn slice_polyline_dash_landing_exactly_on_a_vertex() {
+        let pts = polyline(vec![(0.0, 0.0), (50.0, 0.0), (100.0, 0.0)]);
+        let lines = slice_polyline(&pts, 50.0 * si::M, 70.0 * si::M);
+        // Starts right at the shared vertex, so only the second segment is touched.
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].pt1(), Pt2D::new(50.0, 0.0));
+        assert_eq!(lines[0].pt2(), Pt2D::new(70.0, 0.0));
+    }
+
+    #[test]
+    fn to_geojson_tags_lane_and_marking_features() {
+        let lane = DrawLane {
+            id: LaneID(42),
+            lane_type: map_model::LaneType::Sidewalk,
+            polygon: Polygon::new(&vec![
+                Pt2D::new(0.0, 0.0),
+                Pt2D::new(1.0, 0.0),
+                Pt2D::new(1.0, 1.0),
+                Pt2D::new(0.0, 1.0),
+            ]),
+            start_crossing: Line::new(Pt2D::new(0.0, 0.0), Pt2D::new(1.0, 0.0)),
+            end_crossing: Line::new(Pt2D::new(0.0, 1.0), Pt2D::new(1.0, 1.0)),
+            markings: vec![Marking {
+                // Two disjoint dashes, like calculate_driving_lines produces: nothing should join
+                // the gap between them.
+                lines: vec![
+                    Line::new(Pt2D::new(0.0, 0.5), Pt2D::new(0.3, 0.5)),
+                    Line::new(Pt2D::new(0.7, 0.5), Pt2D::new(1.0, 0.5)),
+                ],
+                color: Colors::SidewalkMarking,
+                thickness: 0.25,
+                round: false,
+                kind: "sidewalk_tile",
+            }],
+            corners: Vec::new(),
+            draw_id_at: Vec::new(),
+        };
+
+        let geojson = lane.to_geojson(&GPSBounds::new());
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0]["properties"]["type"], "lane");
+        assert_eq!(features[0]["properties"]["lane_id"], 42);
+        assert_eq!(features[0]["properties"]["lane_type"], "sidewalk");
+        assert_eq!(features[1]["properties"]["type"], "marking");
+        assert_eq!(features[1]["properties"]["marking_kind"], "sidewalk_tile");
+
+        // MultiLineString with one coordinate list per dash, not a single LineString that would
+        // zigzag through the gap between them.
+        assert_eq!(features[1]["geometry"]["type"], "MultiLineString");
+        let dashes = features[1]["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(dashes.len(), 2);
+        assert_eq!(dashes[0].as_array().unwrap().len(), 2);
+        assert_eq!(dashes[1].as_array().unwrap().len(), 2);
+    }
+
+    // A short crossing line running outward from near `center` at `angle_degrees`, with one
+    // endpoint nearer center than the other so inner_and_outer can tell the two apart.
+    fn fake_crossing(center: Pt2D, angle_degrees: f64) -> Line {
+        let angle = angle_degrees.to_radians();
+        let inner = Pt2D::new(
+            center.x() + 5.0 * angle.cos(),
+            center.y() + 5.0 * angle.sin(),
+        );
+        let outer = Pt2D::new(
+            center.x() + 6.0 * angle.cos(),
+            center.y() + 6.0 * angle.sin(),
+        );
+        Line::new(inner, outer)
+    }
+
+    #[test]
+    fn corners_from_sorted_edges_fills_every_gap_of_a_four_way_intersection() {
+        let center = Pt2D::new(0.0, 0.0);
+        // Four different roads, one sidewalk lane each, evenly spaced around the intersection.
+        let edges = vec![
+            (0.0, map_model::RoadID(1), LaneID(1), fake_crossing(center, 0.0)),
+            (90.0, map_model::RoadID(2), LaneID(2), fake_crossing(center, 90.0)),
+            (180.0, map_model::RoadID(3), LaneID(3), fake_crossing(center, 180.0)),
+            (270.0, map_model::RoadID(4), LaneID(4), fake_crossing(center, 270.0)),
+        ];
+
+        let corners = corners_from_sorted_edges(edges, center);
+
+        // Every adjacent pair (including the wrap from the last back to the first) belongs to a
+        // different road, so all four gaps get filled.
+        assert_eq!(corners.len(), 4);
+        let lane_pairs: Vec<(LaneID, LaneID)> =
+            corners.iter().map(|(l1, l2, _)| (*l1, *l2)).collect();
+        assert_eq!(
+            lane_pairs,
+            vec![
+                (LaneID(1), LaneID(2)),
+                (LaneID(2), LaneID(3)),
+                (LaneID(3), LaneID(4)),
+                (LaneID(4), LaneID(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn corners_from_sorted_edges_skips_same_road_pairs() {
+        let center = Pt2D::new(0.0, 0.0);
+        // A T-intersection: road 1 passes straight through (contributing a sidewalk edge on each
+        // side), road 2 ends here. The two road-1 edges are adjacent once sorted by angle, and
+        // shouldn't get a corner between them -- they're just the two sides of the same sidewalk.
+        let edges = vec![
+            (0.0, map_model::RoadID(1), LaneID(1), fake_crossing(center, 0.0)),
+            (120.0, map_model::RoadID(1), LaneID(2), fake_crossing(center, 120.0)),
+            (240.0, map_model::RoadID(2), LaneID(3), fake_crossing(center, 240.0)),
+        ];
+
+        let corners = corners_from_sorted_edges(edges, center);
+
+        assert_eq!(corners.len(), 2);
+        let lane_pairs: Vec<(LaneID, LaneID)> =
+            corners.iter().map(|(l1, l2, _)| (*l1, *l2)).collect();
+        assert_eq!(
+            lane_pairs,
+            vec![(LaneID(2), LaneID(3)), (LaneID(3), LaneID(1))]
+        );
+    }
+}
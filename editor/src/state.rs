@@ -0,0 +1,59 @@
+// Copyright 2018 Google LLC, licensed under http://www.apache.org/licenses/LICENSE-2.0
+
+use crate::plugins::edit::map_edits::AutoSaveEdits;
+use crate::plugins::PluginCtx;
+use map_model::Map;
+use sim::SimFlags;
+
+// Everything needed to simulate and render one map, independent of whatever plugin is currently
+// active on top of it.
+pub struct PerMapUI {
+    pub map: Map,
+    pub current_flags: SimFlags,
+}
+
+impl PerMapUI {
+    pub fn new(
+        flags: SimFlags,
+        kml: Option<String>,
+        enable_debug_plugins: bool,
+    ) -> (PerMapUI, PluginsPerMap) {
+        let map = Map::new(&flags.load, &flags.edits_name, kml, enable_debug_plugins)
+            .expect("Couldn't load map");
+        let plugins = PluginsPerMap::new(&map);
+        (
+            PerMapUI {
+                map,
+                current_flags: flags,
+            },
+            plugins,
+        )
+    }
+}
+
+// Plugins that run for the lifetime of a map, independent of whichever modal wizard plugin (like
+// EditsManager) happens to be on top. Reconstructed by PerMapUI::new whenever the map reloads.
+pub struct PluginsPerMap {
+    pub autosave_edits: AutoSaveEdits,
+}
+
+impl PluginsPerMap {
+    pub fn new(map: &Map) -> PluginsPerMap {
+        PluginsPerMap {
+            autosave_edits: AutoSaveEdits::new(map),
+        }
+    }
+
+    // Ticks every plugin owned directly by this bag, in priority order. Swaps each plugin out of
+    // `self` before calling it, since Plugin::blocking_event_with_plugins needs `&mut
+    // PluginsPerMap` itself to let plugins reach their siblings (see EditsManager, which reaches
+    // `autosave_edits` this way); the swapped-in placeholder is never observed, since the real
+    // value is always swapped back immediately after.
+    pub fn event(&mut self, ctx: &mut PluginCtx) -> bool {
+        let mut autosave_edits =
+            std::mem::replace(&mut self.autosave_edits, AutoSaveEdits::new(&ctx.primary.map));
+        let blocked = autosave_edits.blocking_event_with_plugins(ctx, self);
+        self.autosave_edits = autosave_edits;
+        blocked
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2018 Google LLC, licensed under http://www.apache.org/licenses/LICENSE-2.0
+
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+
+// A named bundle of manual tweaks to a map. Can be saved to and loaded from disk by name, and
+// optionally annotated with a human-readable description and a link back to wherever the
+// proposal was discussed, so loading someone else's edits shows curated proposals as such instead
+// of just a pile of raw changes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MapEdits {
+    pub edits_name: String,
+    pub proposal_description: Vec<String>,
+    pub proposal_link: Option<String>,
+}
+
+impl MapEdits {
+    pub fn new() -> MapEdits {
+        MapEdits {
+            edits_name: "no_edits".to_string(),
+            proposal_description: Vec::new(),
+            proposal_link: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits_name == "no_edits"
+    }
+
+    pub fn describe(&self) -> String {
+        let mut lines = vec![format!("map edits \"{}\"", self.edits_name)];
+        lines.extend(self.proposal_description.clone());
+        if let Some(ref link) = self.proposal_link {
+            lines.push(format!("See {}", link));
+        }
+        lines.join("\n")
+    }
+
+    fn path(name: &str) -> String {
+        format!("../data/edits/{}.json", name)
+    }
+
+    pub fn save(&self) {
+        fs::write(
+            MapEdits::path(&self.edits_name),
+            abstutil::to_json(self),
+        ).expect("Saving MapEdits failed");
+    }
+
+    pub fn load(name: &str) -> Option<MapEdits> {
+        let contents = fs::read_to_string(MapEdits::path(name)).ok()?;
+        abstutil::from_json(&contents).ok()
+    }
+}